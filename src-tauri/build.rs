@@ -1,15 +1,172 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
 fn main() {
     tauri_build::build();
-    
+
     cxx_build::bridge("src/lib.rs")
         .file("cpp/hello.cpp")
         .file("cpp/math_lib.cpp")
         .flag_if_supported("-std=c++14")
         .compile("hello-world");
-        
+
     println!("cargo:rerun-if-changed=cpp/hello.cpp");
     println!("cargo:rerun-if-changed=cpp/hello.h");
     println!("cargo:rerun-if-changed=cpp/math_lib.cpp");
     println!("cargo:rerun-if-changed=cpp/math_lib.h");
     println!("cargo:rerun-if-changed=src/lib.rs");
+
+    stage_python_scripts();
+
+    #[cfg(target_os = "windows")]
+    embed_windows_metadata();
+}
+
+/// Copies `python_scripts/` next to the build output so `resource_dir()`
+/// finds it during `cargo run`/`tauri dev`. Fails the build if the entry
+/// point script is missing.
+fn stage_python_scripts() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let src_dir = manifest_dir.join("python_scripts");
+    println!("cargo:rerun-if-changed={}", src_dir.display());
+
+    if !src_dir.join("hello.py").exists() {
+        panic!(
+            "python_scripts/hello.py not found at {} -- the embedded Python commands in lib.rs require it to exist before building",
+            src_dir.display()
+        );
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    // OUT_DIR is target/<profile>/build/<crate>-<hash>/out; both the dev
+    // binary and its resource_dir() resolve relative to target/<profile>.
+    let target_dir = out_dir
+        .ancestors()
+        .nth(3)
+        .expect("OUT_DIR has an unexpected layout")
+        .to_path_buf();
+    let dest_dir = target_dir.join("python_scripts");
+
+    copy_dir_recursive(&src_dir, &dest_dir).unwrap_or_else(|e| {
+        panic!(
+            "failed to stage python_scripts into {}: {e}",
+            dest_dir.display()
+        )
+    });
+}
+
+fn copy_dir_recursive(src: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let dest_path = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &dest_path)?;
+        } else {
+            fs::copy(entry.path(), &dest_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Pulls display metadata out of `tauri.conf.json` so the compiled
+/// binary's Windows properties dialog doesn't just show the generic
+/// rustc defaults.
+#[cfg(target_os = "windows")]
+fn embed_windows_metadata() {
+    let conf = fs::read_to_string("tauri.conf.json").expect("failed to read tauri.conf.json");
+    let conf: serde_json::Value =
+        serde_json::from_str(&conf).expect("tauri.conf.json is not valid JSON");
+
+    let product_name = conf["productName"].as_str().unwrap_or("tauri-test");
+    let description = conf["bundle"]["shortDescription"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .unwrap_or(product_name);
+    let version = resolve_version(&conf);
+
+    let mut res = winres::WindowsResource::new();
+    res.set("ProductName", product_name);
+    res.set("FileDescription", description);
+    res.set("FileVersion", &version);
+    res.compile()
+        .expect("failed to embed Windows resource metadata");
+}
+
+/// `tauri.conf.json`'s `version` field may be a literal version string or
+/// a path to a `package.json` to read it from (Tauri's own convention for
+/// keeping a single source of truth). Resolve the latter instead of
+/// embedding the path itself as a bogus version number.
+#[cfg(target_os = "windows")]
+fn resolve_version(conf: &serde_json::Value) -> String {
+    let raw = conf["version"].as_str().unwrap_or("0.0.0");
+
+    if !is_package_json_reference(raw) {
+        return raw.to_string();
+    }
+
+    let package_json_path = PathBuf::from(raw);
+    let package_json = fs::read_to_string(&package_json_path).unwrap_or_else(|e| {
+        panic!(
+            "tauri.conf.json points `version` at {} but it could not be read: {e}",
+            package_json_path.display()
+        )
+    });
+    let package_json: serde_json::Value =
+        serde_json::from_str(&package_json).expect("version-referenced package.json is not valid JSON");
+
+    version_from_package_json(&package_json_path, &package_json)
+}
+
+#[cfg(target_os = "windows")]
+fn is_package_json_reference(raw: &str) -> bool {
+    raw.ends_with(".json")
+}
+
+#[cfg(target_os = "windows")]
+fn version_from_package_json(package_json_path: &Path, package_json: &serde_json::Value) -> String {
+    package_json["version"]
+        .as_str()
+        .unwrap_or_else(|| panic!("{} has no string \"version\" field", package_json_path.display()))
+        .to_string()
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_version_strings_pass_through_unchanged() {
+        let conf = serde_json::json!({ "version": "1.2.3" });
+        assert_eq!(resolve_version(&conf), "1.2.3");
+    }
+
+    #[test]
+    fn missing_version_field_falls_back_to_zero() {
+        let conf = serde_json::json!({});
+        assert_eq!(resolve_version(&conf), "0.0.0");
+    }
+
+    #[test]
+    fn is_package_json_reference_detects_the_path_convention() {
+        assert!(is_package_json_reference("../package.json"));
+        assert!(!is_package_json_reference("1.2.3"));
+    }
+
+    #[test]
+    fn version_from_package_json_reads_the_version_field() {
+        let package_json = serde_json::json!({ "version": "4.5.6" });
+        assert_eq!(
+            version_from_package_json(Path::new("package.json"), &package_json),
+            "4.5.6"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "has no string")]
+    fn version_from_package_json_panics_without_a_version_field() {
+        let package_json = serde_json::json!({});
+        version_from_package_json(Path::new("package.json"), &package_json);
+    }
 }
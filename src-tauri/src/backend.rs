@@ -0,0 +1,20 @@
+/// Selects which runtime executes the Python-backed commands.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PythonBackend {
+    /// Runs `hello.py` in-process via the embedded PyO3 interpreter.
+    Embedded,
+    /// Runs it out-of-process through the bundled Python sidecar binary.
+    Sidecar,
+}
+
+impl PythonBackend {
+    /// Reads `TAURI_TEST_PYTHON_BACKEND` (`"embedded"` | `"sidecar"`),
+    /// defaulting to the embedded interpreter so existing setups keep
+    /// working unless they opt in.
+    pub fn from_env() -> Self {
+        match std::env::var("TAURI_TEST_PYTHON_BACKEND").as_deref() {
+            Ok("sidecar") => PythonBackend::Sidecar,
+            _ => PythonBackend::Embedded,
+        }
+    }
+}
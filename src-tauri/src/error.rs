@@ -0,0 +1,64 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Crate-wide error type returned by every fallible `#[tauri::command]`. Serializes
+/// as `{ kind, message }` so the webview can branch on `kind`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to resolve the app resource directory")]
+    ResourceDir(#[from] tauri::Error),
+    #[error("path contains non-UTF-8 characters")]
+    PathNotUtf8,
+    #[error("failed to import Python module `{module}`")]
+    PythonImport {
+        module: String,
+        #[source]
+        source: pyo3::PyErr,
+    },
+    #[error("call to Python function `{func}` failed")]
+    PythonCall {
+        func: String,
+        #[source]
+        source: pyo3::PyErr,
+    },
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("unknown operation `{0}`")]
+    UnknownOperation(String),
+    #[error("request `{0}` was cancelled")]
+    Cancelled(String),
+    #[error("sidecar call was rejected by the script scope: {0}")]
+    ScopeViolation(String),
+    #[error("Python sidecar process failed: {0}")]
+    Sidecar(String),
+}
+
+impl Error {
+    /// Stable machine-readable discriminant, mirrored into the `kind`
+    /// field so the frontend never has to parse `message`.
+    fn kind(&self) -> &'static str {
+        match self {
+            Error::ResourceDir(_) => "resourceDir",
+            Error::PathNotUtf8 => "pathNotUtf8",
+            Error::PythonImport { .. } => "pythonImport",
+            Error::PythonCall { .. } => "pythonCall",
+            Error::DivisionByZero => "divisionByZero",
+            Error::UnknownOperation(_) => "unknownOperation",
+            Error::Cancelled(_) => "cancelled",
+            Error::ScopeViolation(_) => "scopeViolation",
+            Error::Sidecar(_) => "sidecar",
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Error", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
@@ -1,111 +1,237 @@
+mod backend;
+mod error;
+mod progress;
+mod python_runtime;
+mod sidecar;
+mod tasks;
+
+use std::path::Path;
+
+use backend::PythonBackend;
+use error::Error;
+use progress::{make_progress_callback, ProgressEvent};
 use pyo3::prelude::*;
+use python_runtime::PythonRuntime;
+use sidecar::ScriptScope;
+use tasks::CancellationRegistry;
+use tauri::ipc::Channel;
 use tauri::AppHandle;
 use tauri::Manager;
+use tauri::State;
+
+/// Runs `f` with the GIL held on a dedicated blocking thread. `app_handle`
+/// is moved into the worker so it can fetch the [`PythonRuntime`] state
+/// without borrowing across the `spawn_blocking` boundary.
+async fn run_python<F, R>(app_handle: AppHandle, f: F) -> Result<R, Error>
+where
+    F: FnOnce(Python, &AppHandle, &PythonRuntime) -> Result<R, Error> + Send + 'static,
+    R: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(move || {
+        let runtime = app_handle.state::<PythonRuntime>();
+        Python::with_gil(|py| f(py, &app_handle, &runtime))
+    })
+    .await
+    .expect("python worker thread panicked")
+}
+
+fn expect_string(value: serde_json::Value, func: &str) -> Result<String, Error> {
+    match value {
+        serde_json::Value::String(s) => Ok(s),
+        other => Err(Error::PythonCall {
+            func: func.to_string(),
+            source: pyo3::exceptions::PyValueError::new_err(format!(
+                "expected `{func}` to return a string, got {other}"
+            )),
+        }),
+    }
+}
 
 #[tauri::command]
-async fn process_image(_app_handle: AppHandle, image_data: String) -> Result<String, String> {
+async fn process_image(
+    app_handle: AppHandle,
+    backend: State<'_, PythonBackend>,
+    scope: State<'_, ScriptScope>,
+    image_data: String,
+    on_progress: Channel<ProgressEvent>,
+) -> Result<String, Error> {
 	println!("Rust: Starting Python image processing");
-    
-    // Get the resource directory path outside of the Python context
-    let python_scripts_dir = _app_handle
-        .path()
-        .resource_dir()
-        .map_err(|e| e.to_string())?
-        .join("python_scripts");
-    
-    let python_scripts_str = python_scripts_dir.to_str().ok_or("Failed to convert path to string")?;
-    
-    match Python::with_gil(|py| -> PyResult<String> {
-        // Add python_scripts directory to Python path
-        let sys = py.import("sys")?;
-        sys.getattr("path")?.call_method1("append", (python_scripts_str,))?;
-        
-        let module = py.import("hello")?;
-        let result: String = module
-            .getattr("process_image")?
-            .call1((image_data,))?
-            .extract()?;
-        Ok(result)
-    }) {
-        Ok(result) => Ok(result),
-        Err(e) => Err(e.to_string())
+
+    match *backend {
+        PythonBackend::Embedded => {
+            run_python(app_handle, move |py, handle, runtime| {
+                let callback = make_progress_callback(py, on_progress, None).map_err(|source| Error::PythonCall {
+                    func: "process_image".into(),
+                    source,
+                })?;
+                let result = runtime.invoke(
+                    py,
+                    handle,
+                    "hello",
+                    "process_image",
+                    serde_json::json!([image_data]),
+                    vec![callback],
+                )?;
+                expect_string(result, "process_image")
+            })
+            .await
+        }
+        // The sidecar backend doesn't yet have a progress channel of its
+        // own; it still reports a single final result.
+        PythonBackend::Sidecar => {
+            let args = serde_json::json!({ "image_data": image_data });
+            let result = sidecar::call(&app_handle, &scope, "hello", "process_image", args).await?;
+            serde_json::from_value(result).map_err(|e| Error::Sidecar(e.to_string()))
+        }
     }
 }
 
 #[tauri::command]
-async fn calculate(_app_handle: AppHandle, operation: String, a: f64, b: f64) -> Result<String, String> {
+async fn calculate(
+    app_handle: AppHandle,
+    backend: State<'_, PythonBackend>,
+    scope: State<'_, ScriptScope>,
+    operation: String,
+    a: f64,
+    b: f64,
+) -> Result<String, Error> {
 	println!("Rust: Starting Python calculator with {} {} {}", operation, a, b);
-    
-    // Get the resource directory path outside of the Python context
-    let python_scripts_dir = _app_handle
-        .path()
-        .resource_dir()
-        .map_err(|e| e.to_string())?
-        .join("python_scripts");
-    
-    let python_scripts_str = python_scripts_dir.to_str().ok_or("Failed to convert path to string")?;
-    
-    match Python::with_gil(|py| -> PyResult<String> {
-        // Add python_scripts directory to Python path
-        let sys = py.import("sys")?;
-        sys.getattr("path")?.call_method1("append", (python_scripts_str,))?;
-        
-        let module = py.import("hello")?;
-        let result: String = module
-            .getattr("calculate")?
-            .call1((operation, a, b))?
-            .extract()?;
-        Ok(result)
-    }) {
-        Ok(result) => Ok(result),
-        Err(e) => Err(e.to_string())
+
+    match *backend {
+        PythonBackend::Embedded => {
+            run_python(app_handle, move |py, handle, runtime| {
+                let result = runtime.invoke(
+                    py,
+                    handle,
+                    "hello",
+                    "calculate",
+                    serde_json::json!([operation, a, b]),
+                    vec![],
+                )?;
+                expect_string(result, "calculate")
+            })
+            .await
+        }
+        PythonBackend::Sidecar => {
+            let args = serde_json::json!({ "operation": operation, "a": a, "b": b });
+            let result = sidecar::call(&app_handle, &scope, "hello", "calculate", args).await?;
+            serde_json::from_value(result).map_err(|e| Error::Sidecar(e.to_string()))
+        }
     }
 }
 
 #[tauri::command]
-async fn mif_reader(app_handle: AppHandle, file_path: String, layer_index: i32, variant_index: i32, scale: i32) -> Result<String, String> {
+async fn mif_reader(
+    app_handle: AppHandle,
+    backend: State<'_, PythonBackend>,
+    scope: State<'_, ScriptScope>,
+    cancellation: State<'_, CancellationRegistry>,
+    request_id: String,
+    file_path: String,
+    layer_index: i32,
+    variant_index: i32,
+    scale: i32,
+    on_progress: Channel<ProgressEvent>,
+) -> Result<String, Error> {
     println!("Rust: Starting MIF reader with file: {}", file_path);
-    
-    // Get the resource directory path outside of the Python context
-    let python_scripts_dir = app_handle
-        .path()
-        .resource_dir()
-        .map_err(|e| e.to_string())?
-        .join("python_scripts");
-    
-    let python_scripts_str = python_scripts_dir.to_str().ok_or("Failed to convert path to string")?;
-    println!("Python scripts path: {}", python_scripts_str);
-    
-    match Python::with_gil(|py| -> PyResult<String> {
-        // Print Python's sys.path for debugging
-        let sys = py.import("sys")?;
-        println!("Python sys.path before:");
-        let path = sys.getattr("path")?.extract::<Vec<String>>()?;
-        for p in path.iter() {
-            println!("  {}", p);
+
+    match *backend {
+        PythonBackend::Embedded => {
+            let token = cancellation.register(&request_id);
+            let callback_token = token.clone();
+
+            let result = run_python(app_handle, move |py, handle, runtime| {
+                let callback = make_progress_callback(py, on_progress, Some(callback_token)).map_err(|source| {
+                    Error::PythonCall {
+                        func: "mif_reader".into(),
+                        source,
+                    }
+                })?;
+                let result = runtime.invoke(
+                    py,
+                    handle,
+                    "hello",
+                    "mif_reader",
+                    serde_json::json!([file_path, layer_index, variant_index, scale]),
+                    vec![callback],
+                )?;
+                expect_string(result, "mif_reader")
+            })
+            .await;
+
+            let was_cancelled = token.is_cancelled();
+            cancellation.unregister(&request_id);
+
+            if was_cancelled {
+                Err(Error::Cancelled(request_id))
+            } else {
+                result
+            }
         }
-        
-        // Add python_scripts directory to Python path
-        sys.getattr("path")?.call_method1("append", (python_scripts_str,))?;
-        
-        println!("Python sys.path after:");
-        let path = sys.getattr("path")?.extract::<Vec<String>>()?;
-        for p in path.iter() {
-            println!("  {}", p);
+        PythonBackend::Sidecar => {
+            let args = serde_json::json!({
+                "file_path": file_path,
+                "layer_index": layer_index,
+                "variant_index": variant_index,
+                "scale": scale,
+            });
+            // `sidecar::call` enforces `check_path` for `mif_reader` itself.
+            let result = sidecar::call(&app_handle, &scope, "hello", "mif_reader", args).await?;
+            serde_json::from_value(result).map_err(|e| Error::Sidecar(e.to_string()))
         }
-        
-        let module = py.import("hello")?;
-        let result: String = module
-            .getattr("mif_reader")?
-            .call1((file_path, layer_index, variant_index, scale))?
-            .extract()?;
-        Ok(result)
-    }) {
-        Ok(result) => Ok(result),
-        Err(e) => Err(e.to_string())
     }
 }
 
+/// Generic escape hatch for reaching any Python entry point under
+/// `python_scripts` without writing a dedicated command: `args` is a JSON
+/// array (positional) or object (keyword) that gets converted to Python
+/// values and back. `calculate`/`process_image`/`mif_reader` are thin
+/// typed wrappers over the same [`PythonRuntime::invoke`] this calls.
+///
+/// Unlike those typed wrappers, `module`/`function` here come straight
+/// from the frontend, so this is the one call site that must run every
+/// [`ScriptScope`] check itself rather than trusting a hardcoded literal
+/// -- for both backends, before either one touches Python.
+#[tauri::command]
+async fn py_invoke(
+    app_handle: AppHandle,
+    backend: State<'_, PythonBackend>,
+    scope: State<'_, ScriptScope>,
+    module: String,
+    function: String,
+    args: serde_json::Value,
+) -> Result<serde_json::Value, Error> {
+    scope.check_module(&module)?;
+    scope.check_function(&function)?;
+    if function == "mif_reader" {
+        // The embedded backend bypasses `sidecar::call`, so it has to run
+        // the same path check that function gets there.
+        scope.check_path(Path::new(sidecar::mif_reader_path(&args)?))?;
+    }
+
+    match *backend {
+        PythonBackend::Embedded => {
+            run_python(app_handle, move |py, handle, runtime| {
+                runtime.invoke(py, handle, &module, &function, args, vec![])
+            })
+            .await
+        }
+        PythonBackend::Sidecar => sidecar::call(&app_handle, &scope, &module, &function, args).await,
+    }
+}
+
+/// Requests that the in-flight `mif_reader` call identified by
+/// `request_id` abort. Returns `false` if no such request is running
+/// (e.g. it already finished). Only embedded-backend calls are registered;
+/// with the sidecar backend this always returns `false`.
+///
+/// Takes effect the next time the running script calls `on_progress` --
+/// see [`make_progress_callback`] -- not the instant this returns.
+#[tauri::command]
+fn cancel_mif_reader(cancellation: State<CancellationRegistry>, request_id: String) -> bool {
+    cancellation.cancel(&request_id)
+}
+
 #[cxx::bridge]
 pub mod ffi {
     unsafe extern "C++" {
@@ -125,19 +251,19 @@ fn call_cpp_hello() {
 }
 
 #[tauri::command]
-fn cpp_calculate(operation: &str, a: f64, b: f64) -> Result<f64, String> {
+fn cpp_calculate(operation: &str, a: f64, b: f64) -> Result<f64, Error> {
     match operation {
         "add" => Ok(ffi::add(a, b)),
         "subtract" => Ok(ffi::subtract(a, b)),
         "multiply" => Ok(ffi::multiply(a, b)),
         "divide" => {
             if b == 0.0 {
-                Err("Division by zero!".to_string())
+                Err(Error::DivisionByZero)
             } else {
                 Ok(ffi::divide(a, b))
             }
         },
-        _ => Err(format!("Unknown operation: {}", operation))
+        _ => Err(Error::UnknownOperation(operation.to_string()))
     }
 }
 
@@ -157,10 +283,24 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(PythonRuntime::new())
+        .manage(CancellationRegistry::new())
+        .manage(PythonBackend::from_env())
+        .setup(|app| {
+            let path = app.path();
+            let allowed_roots = [path.document_dir(), path.download_dir()]
+                .into_iter()
+                .filter_map(Result::ok)
+                .collect();
+            app.manage(ScriptScope::new(allowed_roots));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             calculate,
             process_image,
             mif_reader,
+            py_invoke,
+            cancel_mif_reader,
             call_cpp_hello,
             process_file,
             show_alert,
@@ -0,0 +1,67 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyCFunction, PyTuple};
+use serde::Serialize;
+use tauri::ipc::Channel;
+
+use crate::tasks::CancellationToken;
+
+/// Event streamed to the frontend over a [`Channel`] while a long-running
+/// Python operation is in progress.
+#[derive(Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum ProgressEvent {
+    /// Coarse-grained progress update, e.g. `("decoding", 40.0, "...")`.
+    Progress {
+        stage: String,
+        percent: f64,
+        message: String,
+    },
+    /// A single MIF layer/variant decoded and ready to render.
+    Layer {
+        layer_index: i32,
+        variant_index: i32,
+        payload: serde_json::Value,
+    },
+}
+
+/// Builds a Python callable that forwards whatever dict-shaped event it is
+/// called with onto `channel`, e.g. `on_progress({"event": "progress", ...})`.
+///
+/// When `cancellation` is set, each call first checks the token and raises
+/// a `KeyboardInterrupt` if it's been cancelled, so a script that reports
+/// progress per layer/chunk unwinds promptly rather than running to
+/// completion.
+///
+/// This is cooperative, not preemptive: cancellation only takes effect the
+/// next time the script calls `on_progress`. A script that does all of its
+/// work between two calls (or never calls it again after an initial one)
+/// will run to completion regardless of `cancel_mif_reader` -- callers that
+/// need a hard deadline can't rely on this alone and should call
+/// `on_progress` at a granularity fine enough that cancellation lands
+/// promptly (e.g. once per decoded layer, not once per file).
+pub fn make_progress_callback(
+    py: Python<'_>,
+    channel: Channel<ProgressEvent>,
+    cancellation: Option<CancellationToken>,
+) -> PyResult<Py<PyAny>> {
+    let callback = PyCFunction::new_closure(
+        py,
+        None,
+        None,
+        move |args: &Bound<'_, PyTuple>, _kwargs| -> PyResult<()> {
+            if cancellation.as_ref().is_some_and(CancellationToken::is_cancelled) {
+                return Err(pyo3::exceptions::PyKeyboardInterrupt::new_err(
+                    "request was cancelled",
+                ));
+            }
+
+            let event_obj = args.get_item(0)?;
+            let event: ProgressEvent = pythonize::depythonize(&event_obj)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+            channel
+                .send(event)
+                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+        },
+    )?;
+    Ok(callback.into())
+}
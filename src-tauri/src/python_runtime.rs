@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use tauri::{AppHandle, Manager};
+
+use crate::error::Error;
+
+fn python_scripts_dir(app_handle: &AppHandle) -> Result<String, Error> {
+    let dir = app_handle.path().resource_dir()?.join("python_scripts");
+    dir.to_str().map(str::to_owned).ok_or(Error::PathNotUtf8)
+}
+
+fn value_error(func: &str, err: impl std::fmt::Display) -> Error {
+    Error::PythonCall {
+        func: func.to_string(),
+        source: pyo3::exceptions::PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// Managed state wrapping the embedded Python interpreter: patches
+/// `sys.path` at most once and caches each imported module by name.
+#[derive(Default)]
+pub struct PythonRuntime {
+    modules: Mutex<HashMap<String, Py<PyModule>>>,
+    scripts_on_path: Mutex<bool>,
+}
+
+impl PythonRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn ensure_scripts_on_path(&self, py: Python<'_>, app_handle: &AppHandle) -> Result<(), Error> {
+        let mut initialized = self.scripts_on_path.lock().unwrap();
+        if *initialized {
+            return Ok(());
+        }
+
+        let scripts_dir = python_scripts_dir(app_handle)?;
+        let sys = py.import("sys").map_err(|source| Error::PythonImport {
+            module: "sys".into(),
+            source,
+        })?;
+        let path = sys.getattr("path").map_err(|source| Error::PythonImport {
+            module: "sys".into(),
+            source,
+        })?;
+        let already_present: bool = path
+            .call_method1("__contains__", (scripts_dir.as_str(),))
+            .and_then(|r| r.extract())
+            .map_err(|source| Error::PythonImport {
+                module: "sys".into(),
+                source,
+            })?;
+        if !already_present {
+            path.call_method1("append", (scripts_dir.as_str(),))
+                .map_err(|source| Error::PythonImport {
+                    module: "sys".into(),
+                    source,
+                })?;
+        }
+
+        *initialized = true;
+        Ok(())
+    }
+
+    fn module<'py>(
+        &self,
+        py: Python<'py>,
+        app_handle: &AppHandle,
+        module_name: &str,
+    ) -> Result<Bound<'py, PyModule>, Error> {
+        self.ensure_scripts_on_path(py, app_handle)?;
+
+        let mut cached = self.modules.lock().unwrap();
+        if let Some(module) = cached.get(module_name) {
+            return Ok(module.bind(py).clone());
+        }
+
+        let module = py.import(module_name).map_err(|source| Error::PythonImport {
+            module: module_name.to_string(),
+            source,
+        })?;
+        cached.insert(module_name.to_string(), module.clone().unbind());
+        Ok(module)
+    }
+
+    /// Calls `func` on `module_name`: a JSON array becomes positional
+    /// args, an object becomes kwargs (or, when `extra_args` is non-empty,
+    /// a single positional dict), and the return value converts back
+    /// to JSON. `extra_args` are appended as-is after the JSON-derived
+    /// args, for non-JSON values like progress callbacks. Backs both
+    /// `py_invoke` and the typed command wrappers in `lib.rs`.
+    pub fn invoke(
+        &self,
+        py: Python<'_>,
+        app_handle: &AppHandle,
+        module_name: &str,
+        func: &str,
+        args: serde_json::Value,
+        extra_args: Vec<Py<PyAny>>,
+    ) -> Result<serde_json::Value, Error> {
+        let module = self.module(py, app_handle, module_name)?;
+        let callable = module.getattr(func).map_err(|source| Error::PythonCall {
+            func: func.to_string(),
+            source,
+        })?;
+        call_json(py, &callable, func, args, extra_args)
+    }
+}
+
+/// The JSON<->Python argument/return conversion at the core of [`PythonRuntime::invoke`],
+/// pulled out so it can be exercised against a plain Python callable without
+/// needing an [`AppHandle`] or a staged module on disk.
+fn call_json(
+    py: Python<'_>,
+    callable: &Bound<'_, PyAny>,
+    func: &str,
+    args: serde_json::Value,
+    extra_args: Vec<Py<PyAny>>,
+) -> Result<serde_json::Value, Error> {
+    let result = match args {
+        serde_json::Value::Object(fields) if extra_args.is_empty() => {
+            let kwargs = PyDict::new(py);
+            for (key, value) in fields {
+                let py_value =
+                    pythonize::pythonize(py, &value).map_err(|e| value_error(func, e))?;
+                kwargs
+                    .set_item(key, py_value)
+                    .map_err(|source| Error::PythonCall {
+                        func: func.to_string(),
+                        source,
+                    })?;
+            }
+            callable.call((), Some(&kwargs))
+        }
+        serde_json::Value::Array(items) => {
+            let mut tuple_items = items
+                .into_iter()
+                .map(|v| pythonize::pythonize(py, &v).map_err(|e| value_error(func, e)))
+                .collect::<Result<Vec<_>, _>>()?;
+            tuple_items.extend(extra_args.into_iter().map(|a| a.into_bound(py)));
+            callable.call1(PyTuple::new(py, tuple_items))
+        }
+        other => {
+            let mut tuple_items = vec![pythonize::pythonize(py, &other).map_err(|e| value_error(func, e))?];
+            tuple_items.extend(extra_args.into_iter().map(|a| a.into_bound(py)));
+            callable.call1(PyTuple::new(py, tuple_items))
+        }
+    }
+    .map_err(|source| Error::PythonCall {
+        func: func.to_string(),
+        source,
+    })?;
+
+    pythonize::depythonize(&result).map_err(|e| value_error(func, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn array_args_become_positional_parameters() {
+        Python::with_gil(|py| {
+            let add = py
+                .eval_bound("lambda a, b: a + b", None, None)
+                .unwrap();
+            let result = call_json(py, &add, "add", serde_json::json!([2, 3]), vec![]).unwrap();
+            assert_eq!(result, serde_json::json!(5));
+        });
+    }
+
+    #[test]
+    fn object_args_become_keyword_parameters() {
+        Python::with_gil(|py| {
+            let greet = py
+                .eval_bound("lambda name, greeting='hi': f'{greeting}, {name}!'", None, None)
+                .unwrap();
+            let args = serde_json::json!({ "name": "world", "greeting": "hello" });
+            let result = call_json(py, &greet, "greet", args, vec![]).unwrap();
+            assert_eq!(result, serde_json::json!("hello, world!"));
+        });
+    }
+
+    #[test]
+    fn a_bare_scalar_becomes_a_single_positional_argument() {
+        Python::with_gil(|py| {
+            let double = py.eval_bound("lambda n: n * 2", None, None).unwrap();
+            let result = call_json(py, &double, "double", serde_json::json!(21), vec![]).unwrap();
+            assert_eq!(result, serde_json::json!(42));
+        });
+    }
+
+    #[test]
+    fn extra_args_are_appended_after_the_json_derived_positional_args() {
+        Python::with_gil(|py| {
+            let sentinel: Py<PyAny> = 7i64.into_py(py);
+            let concat = py.eval_bound("lambda a, b: (a, b)", None, None).unwrap();
+            let result = call_json(
+                py,
+                &concat,
+                "concat",
+                serde_json::json!([1]),
+                vec![sentinel],
+            )
+            .unwrap();
+            assert_eq!(result, serde_json::json!([1, 7]));
+        });
+    }
+
+    #[test]
+    fn a_python_exception_surfaces_as_a_python_call_error() {
+        Python::with_gil(|py| {
+            let boom = py
+                .eval_bound("lambda: (_ for _ in ()).throw(ValueError('boom'))", None, None)
+                .unwrap();
+            let err = call_json(py, &boom, "boom", serde_json::json!([]), vec![]).unwrap_err();
+            assert!(matches!(err, Error::PythonCall { func, .. } if func == "boom"));
+        });
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::ShellExt;
+
+use crate::error::Error;
+
+/// Allowlist the sidecar spawn is checked against before it runs, modeled
+/// on Tauri's own `ShellScope`: the sidecar can only be asked to run a
+/// function the app named ahead of time, and `mif_reader`'s `file_path`
+/// can't be pointed outside a configured root.
+pub struct ScriptScope {
+    allowed_modules: HashSet<&'static str>,
+    allowed_functions: HashSet<&'static str>,
+    allowed_roots: Vec<PathBuf>,
+}
+
+impl ScriptScope {
+    pub fn new(allowed_roots: Vec<PathBuf>) -> Self {
+        Self {
+            allowed_modules: ["hello"].into_iter().collect(),
+            allowed_functions: ["process_image", "calculate", "mif_reader"].into_iter().collect(),
+            allowed_roots,
+        }
+    }
+
+    pub fn check_module(&self, module: &str) -> Result<(), Error> {
+        if self.allowed_modules.contains(module) {
+            Ok(())
+        } else {
+            Err(Error::ScopeViolation(format!(
+                "module `{module}` is not on the sidecar allowlist"
+            )))
+        }
+    }
+
+    pub fn check_function(&self, function: &str) -> Result<(), Error> {
+        if self.allowed_functions.contains(function) {
+            Ok(())
+        } else {
+            Err(Error::ScopeViolation(format!(
+                "function `{function}` is not on the sidecar allowlist"
+            )))
+        }
+    }
+
+    pub fn check_path(&self, path: &Path) -> Result<(), Error> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|_| Error::ScopeViolation(format!("path `{}` does not exist", path.display())))?;
+        if self.allowed_roots.iter().any(|root| canonical.starts_with(root)) {
+            Ok(())
+        } else {
+            Err(Error::ScopeViolation(format!(
+                "path `{}` is outside the allowed sidecar roots",
+                path.display()
+            )))
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SidecarRequest<'a> {
+    module: &'a str,
+    function: &'a str,
+    args: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct SidecarResponse {
+    result: Option<serde_json::Value>,
+    error: Option<String>,
+}
+
+/// Pulls `mif_reader`'s `file_path` argument out of its JSON-encoded
+/// call args, whichever shape the caller used (the typed `mif_reader`
+/// command sends an object, `py_invoke` may send either).
+pub fn mif_reader_path(args: &serde_json::Value) -> Result<&str, Error> {
+    let path = match args {
+        serde_json::Value::Object(fields) => fields.get("file_path").and_then(|v| v.as_str()),
+        serde_json::Value::Array(items) => items.first().and_then(|v| v.as_str()),
+        _ => None,
+    };
+    path.ok_or_else(|| Error::ScopeViolation("mif_reader requires a string `file_path` argument".into()))
+}
+
+/// Spawns the bundled standalone Python sidecar, writes a JSON request for
+/// `function` on stdin, and reads a single JSON response off stdout.
+pub async fn call(
+    app_handle: &AppHandle,
+    scope: &ScriptScope,
+    module: &str,
+    function: &str,
+    args: serde_json::Value,
+) -> Result<serde_json::Value, Error> {
+    scope.check_module(module)?;
+    scope.check_function(function)?;
+    if function == "mif_reader" {
+        scope.check_path(Path::new(mif_reader_path(&args)?))?;
+    }
+
+    let request = serde_json::to_vec(&SidecarRequest { module, function, args })
+        .map_err(|e| Error::Sidecar(e.to_string()))?;
+
+    let (mut rx, mut child) = app_handle
+        .shell()
+        .sidecar("python-hello")
+        .map_err(|e| Error::Sidecar(e.to_string()))?
+        .spawn()
+        .map_err(|e| Error::Sidecar(e.to_string()))?;
+
+    child
+        .write(&request)
+        .map_err(|e| Error::Sidecar(e.to_string()))?;
+    drop(child);
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(chunk) => stdout.extend(chunk),
+            CommandEvent::Stderr(chunk) => stderr.extend(chunk),
+            CommandEvent::Error(message) => return Err(Error::Sidecar(message)),
+            CommandEvent::Terminated(status) if status.code != Some(0) => {
+                return Err(Error::Sidecar(String::from_utf8_lossy(&stderr).into_owned()));
+            }
+            _ => {}
+        }
+    }
+
+    let response: SidecarResponse =
+        serde_json::from_slice(&stdout).map_err(|e| Error::Sidecar(e.to_string()))?;
+    match response.error {
+        Some(message) => Err(Error::Sidecar(message)),
+        None => Ok(response.result.unwrap_or(serde_json::Value::Null)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(roots: Vec<PathBuf>) -> ScriptScope {
+        ScriptScope::new(roots)
+    }
+
+    #[test]
+    fn check_module_rejects_anything_off_the_allowlist() {
+        let scope = scope(vec![]);
+        assert!(scope.check_module("hello").is_ok());
+        assert!(scope.check_module("os").is_err());
+    }
+
+    #[test]
+    fn check_function_rejects_anything_off_the_allowlist() {
+        let scope = scope(vec![]);
+        assert!(scope.check_function("mif_reader").is_ok());
+        assert!(scope.check_function("eval").is_err());
+    }
+
+    #[test]
+    fn check_path_accepts_paths_under_an_allowed_root() {
+        let dir = std::env::temp_dir().join(format!(
+            "tauri-test-scope-ok-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("layer.mif");
+        std::fs::write(&file, b"data").unwrap();
+
+        let scope = scope(vec![dir.canonicalize().unwrap()]);
+        assert!(scope.check_path(&file).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn check_path_rejects_paths_outside_every_allowed_root() {
+        let allowed = std::env::temp_dir().join(format!(
+            "tauri-test-scope-allowed-{}",
+            std::process::id()
+        ));
+        let outside = std::env::temp_dir().join(format!(
+            "tauri-test-scope-outside-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&allowed).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        let file = outside.join("layer.mif");
+        std::fs::write(&file, b"data").unwrap();
+
+        let scope = scope(vec![allowed.canonicalize().unwrap()]);
+        assert!(scope.check_path(&file).is_err());
+
+        std::fs::remove_dir_all(&allowed).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn check_path_rejects_a_nonexistent_path() {
+        let scope = scope(vec![std::env::temp_dir()]);
+        assert!(scope.check_path(Path::new("/does/not/exist/anywhere.mif")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_path_rejects_a_symlink_that_escapes_the_allowed_root() {
+        let allowed = std::env::temp_dir().join(format!(
+            "tauri-test-scope-symlink-allowed-{}",
+            std::process::id()
+        ));
+        let outside = std::env::temp_dir().join(format!(
+            "tauri-test-scope-symlink-outside-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&allowed).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        let secret = outside.join("secret.mif");
+        std::fs::write(&secret, b"data").unwrap();
+        let link = allowed.join("escape.mif");
+        std::os::unix::fs::symlink(&secret, &link).unwrap();
+
+        let scope = scope(vec![allowed.canonicalize().unwrap()]);
+        // `canonicalize` resolves the symlink to its real, out-of-root
+        // target, so the containment check still has to catch it.
+        assert!(scope.check_path(&link).is_err());
+
+        std::fs::remove_dir_all(&allowed).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn mif_reader_path_reads_file_path_from_an_object_or_an_array() {
+        let object_args = serde_json::json!({ "file_path": "/a/b.mif", "layer_index": 0 });
+        assert_eq!(mif_reader_path(&object_args).unwrap(), "/a/b.mif");
+
+        let array_args = serde_json::json!(["/a/b.mif", 0, 0, 1]);
+        assert_eq!(mif_reader_path(&array_args).unwrap(), "/a/b.mif");
+    }
+
+    #[test]
+    fn mif_reader_path_rejects_args_missing_a_file_path() {
+        assert!(mif_reader_path(&serde_json::json!({ "layer_index": 0 })).is_err());
+        assert!(mif_reader_path(&serde_json::json!([])).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Lets a running background task poll whether its caller asked for it to
+/// stop.
+#[derive(Clone)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Managed state mapping a frontend-supplied request id to the
+/// [`CancellationToken`] of whichever task is running it.
+#[derive(Default)]
+pub struct CancellationRegistry {
+    tokens: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl CancellationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `request_id` as in flight and returns a token the task can
+    /// poll for cancellation.
+    pub fn register(&self, request_id: &str) -> CancellationToken {
+        let flag = Arc::new(AtomicBool::new(false));
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(request_id.to_string(), flag.clone());
+        CancellationToken(flag)
+    }
+
+    /// Removes `request_id` once its task has finished, cancelled or not.
+    pub fn unregister(&self, request_id: &str) {
+        self.tokens.lock().unwrap().remove(request_id);
+    }
+
+    /// Marks `request_id` as cancelled. Returns `false` if no such request
+    /// is currently in flight.
+    pub fn cancel(&self, request_id: &str) -> bool {
+        match self.tokens.lock().unwrap().get(request_id) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}